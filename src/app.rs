@@ -1,40 +1,341 @@
+use super::cache_params::compute_image_hash;
 use super::crosshair::*;
+use super::renderer::{FrameView, Renderer, RendererError, RendererKind, create_renderer};
 use smithay_client_toolkit::{
-    compositor::CompositorHandler,
+    compositor::{CompositorHandler, CompositorState, Region},
     output::{OutputHandler, OutputState},
     registry::RegistryState,
     shell::{
         WaylandSurface,
-        wlr_layer::{Anchor, LayerShellHandler, LayerSurface, LayerSurfaceConfigure},
+        wlr_layer::{
+            Anchor, KeyboardInteractivity, Layer, LayerShell, LayerShellHandler, LayerSurface,
+            LayerSurfaceConfigure,
+        },
     },
     shm::{
         Shm, ShmHandler,
-        slot::{ActivateSlotError, CreateBufferError, SlotPool},
+        slot::{Buffer, SlotPool},
     },
 };
-use std::{num::NonZeroU32, time::Instant};
-use thiserror::Error;
+use std::{
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
 use wayland_client::{
-    Connection, QueueHandle,
+    Connection, Dispatch, QueueHandle,
     protocol::{wl_output, wl_shm, wl_surface},
 };
+use wayland_protocols_wlr::screencopy::v1::client::{
+    zwlr_screencopy_frame_v1::{self, ZwlrScreencopyFrameV1},
+    zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1,
+};
+
+/// Size (in logical pixels) of the square region sampled around the crosshair
+/// center to estimate what's behind it.
+const SAMPLE_SIZE: i32 = 32;
+/// Minimum time between background samples, so we're not screencopying every frame.
+const SAMPLE_INTERVAL_MS: u128 = 500;
+/// Below this luminance we switch to the light/inverted variant.
+const DARK_LUMINANCE_THRESHOLD: f32 = 85.0;
+/// Above this luminance we switch back to the normal variant. Kept apart
+/// from `DARK_LUMINANCE_THRESHOLD` as hysteresis so a background hovering
+/// around the boundary doesn't flicker between variants.
+const LIGHT_LUMINANCE_THRESHOLD: f32 = 105.0;
+/// Minimum time between `--watch` mtime/hash checks, so we're not hashing the
+/// source file every frame.
+const WATCH_INTERVAL_MS: u128 = 500;
+
+/// Per-`wl_output` surface state. One of these exists for every monitor the
+/// crosshair is drawn on.
+///
+/// Field order matters here: `WgpuRenderer` builds its `wgpu::Surface` from the raw
+/// pointer of `layer`'s `wl_surface`, so `renderer` must be dropped (and thus destroy
+/// that `wgpu::Surface`) before `layer` destroys the `wl_surface` it points at. Rust
+/// drops struct fields in declaration order, so `renderer` is listed first.
+pub struct OutputSurface {
+    pub renderer: Box<dyn Renderer>,
+    pub output: wl_output::WlOutput,
+    pub layer: LayerSurface,
+    pub width: u32,
+    pub height: u32,
+    pub first_configure: bool,
+    pub positioned: bool,
+
+    /// Adaptive contrast is tracked per output, since each monitor can have different
+    /// content behind the crosshair.
+    pub last_sample: Instant,
+    pub dark_background: bool,
+    pub pending_capture: Option<PendingCapture>,
+}
+
+/// An in-flight `zwlr_screencopy_frame_v1` capture used to sample the
+/// background luminance.
+pub struct PendingCapture {
+    frame: ZwlrScreencopyFrameV1,
+    state: CaptureState,
+}
+
+enum CaptureState {
+    AwaitingBuffer,
+    Copying {
+        pool: SlotPool,
+        buffer: Buffer,
+        width: u32,
+        height: u32,
+    },
+}
 
 pub struct App {
     pub registry_state: RegistryState,
     pub output_state: OutputState,
+    pub compositor_state: CompositorState,
+    pub layer_shell: LayerShell,
     pub shm: Shm,
+    pub conn: Connection,
+    pub renderer_kind: RendererKind,
+    /// Only present when the compositor implements the wlr screencopy
+    /// protocol; adaptive contrast is silently disabled otherwise.
+    pub screencopy_manager: Option<ZwlrScreencopyManagerV1>,
 
     pub exit: bool,
-    pub first_configure: bool,
-    pub pool: SlotPool,
-    pub width: u32,
-    pub height: u32,
-    pub layer: LayerSurface,
+    pub surfaces: Vec<OutputSurface>,
 
     pub image: CrosshairImage,
+    pub image_w: u32,
+    pub image_h: u32,
     pub target_x: u32,
     pub target_y: u32,
-    pub positioned: bool,
+    /// If set, only the `wl_output` with this name gets a surface.
+    pub output_name: Option<String>,
+
+    pub opacity: f32,
+    /// Source path `image` was loaded from; re-read on every `--watch` check.
+    pub image_path: String,
+    /// blake3 hash of `image_path` as of the last (re)load, used to detect content changes.
+    pub image_hash: String,
+    pub watch: bool,
+    /// Raw `--target-x`/`--target-y` values, if the user gave them explicitly. When unset,
+    /// the target recenters on the image midpoint, which has to be recomputed on every reload.
+    pub target_x_arg: Option<u32>,
+    pub target_y_arg: Option<u32>,
+    pub last_watch_check: Instant,
+}
+
+impl App {
+    /// Creates and maps a layer surface for `output`, unless `output_name`
+    /// restricts us to a different monitor.
+    fn add_output(&mut self, qh: &QueueHandle<Self>, output: wl_output::WlOutput) {
+        if let Some(wanted) = &self.output_name {
+            let name = self.output_state.info(&output).and_then(|info| info.name);
+            if name.as_deref() != Some(wanted.as_str()) {
+                return;
+            }
+        }
+
+        let surface = self.compositor_state.create_surface(qh);
+        let layer = self.layer_shell.create_layer_surface(
+            qh,
+            surface,
+            Layer::Overlay,
+            Some("rcrosshair"),
+            Some(&output),
+        );
+
+        let region = match Region::new(&self.compositor_state) {
+            Ok(region) => region,
+            Err(e) => {
+                log::error!("Failed to create input region: {}", e);
+                return;
+            }
+        };
+        let wl_region = region.wl_region();
+        layer.wl_surface().set_input_region(Some(wl_region));
+        wl_region.destroy();
+
+        layer.set_exclusive_zone(-1);
+        layer.set_keyboard_interactivity(KeyboardInteractivity::None);
+        layer.set_size(self.image_w, self.image_h);
+        layer.commit();
+
+        let renderer = match create_renderer(
+            self.renderer_kind,
+            &self.conn,
+            &self.shm,
+            layer.wl_surface(),
+            self.image_w,
+            self.image_h,
+            &self.image,
+        ) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                log::error!("Failed to create renderer for output: {}", e);
+                return;
+            }
+        };
+
+        self.surfaces.push(OutputSurface {
+            output,
+            layer,
+            renderer,
+            width: self.image_w,
+            height: self.image_h,
+            first_configure: true,
+            positioned: false,
+            last_sample: Instant::now() - Duration::from_secs(1),
+            dark_background: false,
+            pending_capture: None,
+        });
+    }
+
+    /// Kicks off a screencopy capture of the region behind the crosshair on every output
+    /// that doesn't already have one in flight and is due for a re-sample. Luminance (and
+    /// therefore which variant is drawn) is tracked per output, since each monitor can have
+    /// different content behind the crosshair.
+    fn maybe_sample_background(&mut self, qh: &QueueHandle<Self>) {
+        let Some(manager) = &self.screencopy_manager else {
+            return;
+        };
+
+        for idx in 0..self.surfaces.len() {
+            let surface = &self.surfaces[idx];
+            if surface.pending_capture.is_some() {
+                continue;
+            }
+            if surface.last_sample.elapsed().as_millis() < SAMPLE_INTERVAL_MS {
+                continue;
+            }
+            let Some(info) = self.output_state.info(&surface.output) else {
+                continue;
+            };
+            let (screen_w, screen_h) = info.logical_size.unwrap_or((1920, 1080));
+
+            let x = (screen_w / 2 - SAMPLE_SIZE / 2).max(0);
+            let y = (screen_h / 2 - SAMPLE_SIZE / 2).max(0);
+
+            let output = surface.output.clone();
+            let frame = manager.capture_output_region(
+                0,
+                &output,
+                x,
+                y,
+                SAMPLE_SIZE,
+                SAMPLE_SIZE,
+                qh,
+                output.clone(),
+            );
+
+            let surface = &mut self.surfaces[idx];
+            surface.last_sample = Instant::now();
+            surface.pending_capture = Some(PendingCapture {
+                frame,
+                state: CaptureState::AwaitingBuffer,
+            });
+        }
+    }
+
+    /// Applies hysteresis around the luminance thresholds for a single output and, if its
+    /// variant actually flips, redraws just that output.
+    fn update_background_luminance(&mut self, idx: usize, luminance: f32, qh: &QueueHandle<Self>) {
+        let dark_background = self.surfaces[idx].dark_background;
+        let now_dark = if dark_background {
+            luminance < LIGHT_LUMINANCE_THRESHOLD
+        } else {
+            luminance < DARK_LUMINANCE_THRESHOLD
+        };
+
+        if now_dark == dark_background {
+            return;
+        }
+        self.surfaces[idx].dark_background = now_dark;
+
+        if let Err(e) = self.draw(qh, idx) {
+            log::error!("Failed to redraw after contrast change: {}", e);
+        }
+    }
+
+    /// Called from frame callbacks; hashes `image_path` at most once per `WATCH_INTERVAL_MS`
+    /// and hot-reloads the crosshair when its contents have changed. Only does anything with
+    /// `--watch`.
+    fn maybe_check_watch(&mut self, qh: &QueueHandle<Self>) {
+        if !self.watch {
+            return;
+        }
+        if self.last_watch_check.elapsed().as_millis() < WATCH_INTERVAL_MS {
+            return;
+        }
+        self.last_watch_check = Instant::now();
+
+        let hash = match compute_image_hash(&self.image_path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                log::warn!("Failed to hash {}: {}", self.image_path, e);
+                return;
+            }
+        };
+        if hash == self.image_hash {
+            return;
+        }
+        self.image_hash = hash;
+        self.reload_image(qh);
+    }
+
+    /// Re-runs `load_image` against `image_path` and pushes the result into every surface,
+    /// resizing and repositioning each one for the new dimensions.
+    fn reload_image(&mut self, qh: &QueueHandle<Self>) {
+        let (w, h, image) = match load_image(&self.image_path, self.opacity) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                log::error!("Failed to reload {}: {}", self.image_path, e);
+                return;
+            }
+        };
+
+        self.image = image;
+        self.image_w = w;
+        self.image_h = h;
+        self.target_x = self.target_x_arg.unwrap_or(w / 2);
+        self.target_y = self.target_y_arg.unwrap_or(h / 2);
+
+        for idx in 0..self.surfaces.len() {
+            if let Err(e) = self.surfaces[idx].renderer.resize(&self.shm, w, h) {
+                log::error!("Failed to resize renderer after reload: {}", e);
+                continue;
+            }
+            self.surfaces[idx].renderer.reload(w, h, &self.image);
+            self.surfaces[idx].width = w;
+            self.surfaces[idx].height = h;
+            self.surfaces[idx].layer.set_size(w, h);
+
+            if let Some(info) = self.output_state.info(&self.surfaces[idx].output) {
+                let (screen_w, screen_h) = info.logical_size.unwrap_or((1920, 1080));
+                let left_margin = (screen_w / 2) - self.target_x as i32;
+                let top_margin = (screen_h / 2) - self.target_y as i32;
+
+                let surface = &mut self.surfaces[idx];
+                surface.layer.set_anchor(Anchor::TOP | Anchor::LEFT);
+                surface.layer.set_margin(top_margin, 0, 0, left_margin);
+            }
+            self.surfaces[idx].layer.commit();
+
+            if let Err(e) = self.draw(qh, idx) {
+                log::error!("Failed to redraw after reload: {}", e);
+            }
+        }
+    }
+}
+
+/// Mean of `L = 0.299*R + 0.587*G + 0.114*B` over an `Argb8888` shm buffer.
+fn average_luminance(data: &[u8], width: u32, height: u32) -> f32 {
+    let pixel_count = (width * height).max(1) as f32;
+    let total: f32 = data
+        .chunks_exact(4)
+        .map(|px| {
+            let (b, g, r) = (px[0] as f32, px[1] as f32, px[2] as f32);
+            0.299 * r + 0.587 * g + 0.114 * b
+        })
+        .sum();
+
+    total / pixel_count
 }
 
 impl CompositorHandler for App {
@@ -60,28 +361,34 @@ impl CompositorHandler for App {
         &mut self,
         _conn: &Connection,
         qh: &QueueHandle<Self>,
-        _surface: &wl_surface::WlSurface,
+        surface: &wl_surface::WlSurface,
         _time: u32,
     ) {
-        match self.image {
-            CrosshairImage::Gif(ref mut image) => {
-                let now = Instant::now();
-                let elapsed = now.duration_since(image.last_frame_time);
-                let delay_ms = image.frames[image.current_frame].delay_ms;
-
-                if elapsed.as_millis() >= delay_ms {
-                    image.current_frame = (image.current_frame + 1) % image.frames.len();
-                    image.last_frame_time = now;
-                }
-
-                if let Err(e) = self.draw(qh) {
-                    log::error!("Failed to draw frame: {}", e);
-                }
-            }
-            CrosshairImage::Static(_) => {
-                // Ignore
+        let Some(idx) = self
+            .surfaces
+            .iter()
+            .position(|s| s.layer.wl_surface() == surface)
+        else {
+            return;
+        };
+
+        if let CrosshairImage::Animated(ref mut image) = self.image {
+            let now = Instant::now();
+            let elapsed = now.duration_since(image.last_frame_time);
+            let delay_ms = image.frames[image.current_frame].delay_ms;
+
+            if elapsed.as_millis() >= delay_ms {
+                image.current_frame = (image.current_frame + 1) % image.frames.len();
+                image.last_frame_time = now;
             }
         }
+
+        self.maybe_sample_background(qh);
+        self.maybe_check_watch(qh);
+
+        if let Err(e) = self.draw(qh, idx) {
+            log::error!("Failed to draw frame: {}", e);
+        }
     }
 
     fn surface_enter(
@@ -111,9 +418,10 @@ impl OutputHandler for App {
     fn new_output(
         &mut self,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        qh: &QueueHandle<Self>,
+        output: wl_output::WlOutput,
     ) {
+        self.add_output(qh, output);
     }
 
     fn update_output(
@@ -128,66 +436,69 @@ impl OutputHandler for App {
         &mut self,
         _conn: &Connection,
         _qh: &QueueHandle<Self>,
-        _output: wl_output::WlOutput,
+        output: wl_output::WlOutput,
     ) {
+        self.surfaces.retain(|s| s.output != output);
     }
 }
 
 impl LayerShellHandler for App {
-    fn closed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _layer: &LayerSurface) {
-        self.exit = true;
+    fn closed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, layer: &LayerSurface) {
+        self.surfaces.retain(|s| &s.layer != layer);
+        if self.surfaces.is_empty() {
+            self.exit = true;
+        }
     }
 
     fn configure(
         &mut self,
         _conn: &Connection,
         qh: &QueueHandle<Self>,
-        _layer: &LayerSurface,
+        layer: &LayerSurface,
         configure: LayerSurfaceConfigure,
         _serial: u32,
     ) {
-        let new_w = NonZeroU32::new(configure.new_size.0).map_or(self.width, NonZeroU32::get);
-        let new_h = NonZeroU32::new(configure.new_size.1).map_or(self.height, NonZeroU32::get);
-
-        // Resize pool only if significantly larger
-        let needed = (new_w * new_h * 4) as usize;
-        if needed > self.pool.len() {
-            if let Ok(new_pool) = SlotPool::new(
-                needed.max((self.width * self.height * 4) as usize),
-                &self.shm,
-            ) {
-                self.pool = new_pool;
-            } else {
-                log::error!("Failed to resize shm pool");
-            }
+        let Some(idx) = self.surfaces.iter().position(|s| &s.layer == layer) else {
+            return;
+        };
+
+        let (width, height, first_configure) = {
+            let surface = &self.surfaces[idx];
+            (surface.width, surface.height, surface.first_configure)
+        };
+
+        let new_w = NonZeroU32::new(configure.new_size.0).map_or(width, NonZeroU32::get);
+        let new_h = NonZeroU32::new(configure.new_size.1).map_or(height, NonZeroU32::get);
+
+        if let Err(e) = self.surfaces[idx].renderer.resize(&self.shm, new_w, new_h) {
+            log::error!("Failed to resize renderer: {}", e);
         }
 
-        let size_changed = new_w != self.width || new_h != self.height;
+        let size_changed = new_w != width || new_h != height;
 
-        self.width = new_w;
-        self.height = new_h;
+        self.surfaces[idx].width = new_w;
+        self.surfaces[idx].height = new_h;
 
-        if let Some(output) = self.output_state.outputs().next()
-            && let Some(info) = self.output_state.info(&output)
-        {
+        if let Some(info) = self.output_state.info(&self.surfaces[idx].output) {
             let (screen_w, screen_h) = info.logical_size.unwrap_or((1920, 1080));
 
             let left_margin = (screen_w / 2) - self.target_x as i32;
             let top_margin = (screen_h / 2) - self.target_y as i32;
 
-            self.layer.set_anchor(Anchor::TOP | Anchor::LEFT);
-            self.layer.set_margin(top_margin, 0, 0, left_margin);
-            self.positioned = true;
-            self.layer.commit();
+            let surface = &mut self.surfaces[idx];
+            surface.layer.set_anchor(Anchor::TOP | Anchor::LEFT);
+            surface.layer.set_margin(top_margin, 0, 0, left_margin);
+            surface.positioned = true;
+            surface.layer.commit();
         }
 
-        if self.first_configure || size_changed {
-            if !self.positioned {
-                self.layer.commit();
+        if first_configure || size_changed {
+            if !self.surfaces[idx].positioned {
+                self.surfaces[idx].layer.commit();
             }
 
-            self.first_configure = false;
-            if let Err(e) = self.draw(qh) {
+            self.surfaces[idx].first_configure = false;
+            if let Err(e) = self.draw(qh, idx) {
                 log::error!("Draw failed after configure: {}", e);
             }
         }
@@ -200,55 +511,153 @@ impl ShmHandler for App {
     }
 }
 
-#[derive(Debug, Error)]
-enum DrawError {
-    #[error("Failed to create buffer: {0}")]
-    CreateBuffer(#[from] CreateBufferError),
-    #[error("Failed to activate slot: {0}")]
-    ActivateSlot(#[from] ActivateSlotError),
+impl Dispatch<ZwlrScreencopyManagerV1, ()> for App {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZwlrScreencopyManagerV1,
+        _event: <ZwlrScreencopyManagerV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // zwlr_screencopy_manager_v1 has no events.
+    }
 }
 
-impl App {
-    fn draw(&mut self, qh: &QueueHandle<Self>) -> Result<(), DrawError> {
-        let width = self.width;
-        let height = self.height;
-        let stride = self.width * 4;
-
-        let (buffer, canvas) = self.pool.create_buffer(
-            width as i32,
-            height as i32,
-            stride as i32,
-            wl_shm::Format::Argb8888,
-        )?;
-
-        // Draw to the window:
-        // Clear canvas to transparent black
-        canvas.fill(0);
-
-        match self.image {
-            CrosshairImage::Gif(ref crosshair) => {
-                let frame = &crosshair.frames[crosshair.current_frame];
-                canvas[..frame.data.len()].copy_from_slice(&frame.data);
+impl Dispatch<ZwlrScreencopyFrameV1, wl_output::WlOutput> for App {
+    fn event(
+        state: &mut Self,
+        frame: &ZwlrScreencopyFrameV1,
+        event: zwlr_screencopy_frame_v1::Event,
+        output: &wl_output::WlOutput,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        // The capture this event belongs to is identified by the `wl_output` it was
+        // requested for, not by position, since `self.surfaces` can be reordered or
+        // shrink (hotplug) while a capture is in flight.
+        let Some(idx) = state.surfaces.iter().position(|s| &s.output == output) else {
+            return;
+        };
+
+        match event {
+            zwlr_screencopy_frame_v1::Event::Buffer {
+                format,
+                width,
+                height,
+                stride,
+            } => {
+                let Ok(format) = format.into_result() else {
+                    return;
+                };
+                if format != wl_shm::Format::Argb8888 && format != wl_shm::Format::Xrgb8888 {
+                    // We only know how to read back Argb8888/Xrgb8888; ignore other offers.
+                    return;
+                }
+                if !matches!(
+                    state.surfaces[idx].pending_capture.as_ref().map(|c| &c.state),
+                    Some(CaptureState::AwaitingBuffer)
+                ) {
+                    return;
+                }
 
-                // Request our next frame
-                self.layer
-                    .wl_surface()
-                    .frame(qh, self.layer.wl_surface().clone());
+                let buffer = match SlotPool::new((stride * height) as usize, &state.shm) {
+                    // The reply buffer's format/width/height/stride must match what this
+                    // `buffer` event advertised, or the compositor raises `invalid_buffer`.
+                    Ok(mut pool) => pool
+                        .create_buffer(width as i32, height as i32, stride as i32, format)
+                        .map(|(buffer, _canvas)| (pool, buffer))
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                match buffer {
+                    Ok((pool, buffer)) => {
+                        frame.copy(buffer.wl_buffer());
+                        if let Some(capture) = state.surfaces[idx].pending_capture.as_mut() {
+                            capture.state = CaptureState::Copying {
+                                pool,
+                                buffer,
+                                width,
+                                height,
+                            };
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to allocate screencopy buffer: {}", e);
+                        state.surfaces[idx].pending_capture = None;
+                    }
+                }
             }
-            CrosshairImage::Static(ref frame) => {
-                canvas[..frame.data.len()].copy_from_slice(&frame.data);
+            zwlr_screencopy_frame_v1::Event::Ready { .. } => {
+                let Some(capture) = state.surfaces[idx].pending_capture.take() else {
+                    return;
+                };
+                if let CaptureState::Copying {
+                    pool,
+                    buffer,
+                    width,
+                    height,
+                } = capture.state
+                    && let Some(canvas) = pool.canvas(&buffer)
+                {
+                    let luminance = average_luminance(canvas, width, height);
+                    state.update_background_luminance(idx, luminance, qh);
+                }
             }
+            zwlr_screencopy_frame_v1::Event::Failed => {
+                state.surfaces[idx].pending_capture = None;
+            }
+            _ => {}
         }
+    }
+}
 
-        // Damage the entire window
-        self.layer
-            .wl_surface()
-            .damage_buffer(0, 0, width as i32, height as i32);
-
-        // Attach and commit to present.
-        buffer.attach_to(self.layer.wl_surface())?;
-        self.layer.commit();
+impl App {
+    fn draw(&mut self, qh: &QueueHandle<Self>, idx: usize) -> Result<(), RendererError> {
+        let width = self.surfaces[idx].width;
+        let height = self.surfaces[idx].height;
+        let dark_background = self.surfaces[idx].dark_background;
 
-        Ok(())
+        let (index, bytes) = match self.image {
+            CrosshairImage::Animated(ref crosshair) => {
+                let frame = &crosshair.frames[crosshair.current_frame];
+                let bytes = if dark_background {
+                    &frame.inverted
+                } else {
+                    &frame.data
+                };
+                (crosshair.current_frame, bytes)
+            }
+            CrosshairImage::Static(ref frame) => {
+                let bytes = if dark_background {
+                    &frame.inverted
+                } else {
+                    &frame.data
+                };
+                (0, bytes)
+            }
+        };
+
+        // Animated crosshairs need a steady stream of frame callbacks to advance; adaptive
+        // contrast and `--watch` piggyback on that same cadence to re-sample the background
+        // and re-hash the source file periodically.
+        let request_next_frame = matches!(self.image, CrosshairImage::Animated(_))
+            || self.screencopy_manager.is_some()
+            || self.watch;
+
+        let surface = &mut self.surfaces[idx];
+        surface.renderer.draw(
+            qh,
+            &surface.layer,
+            width,
+            height,
+            FrameView {
+                index,
+                dark_background,
+                bytes,
+                request_next_frame,
+            },
+        )
     }
 }
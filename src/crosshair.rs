@@ -1,6 +1,6 @@
 use image::{
-    AnimationDecoder, GenericImageView, ImageDecoder, ImageFormat, RgbaImage,
-    codecs::gif::GifDecoder,
+    AnimationDecoder, GenericImageView, ImageFormat, RgbaImage,
+    codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
 };
 use std::{
     fs::File,
@@ -9,27 +9,33 @@ use std::{
     time::Instant,
 };
 
-pub struct GifFrame {
+pub struct AnimatedFrame {
     pub data: Vec<u8>,
+    /// Same frame with its colors inverted, used for the adaptive-contrast
+    /// variant when the crosshair sits over a dark background.
+    pub inverted: Vec<u8>,
     pub delay_ms: u128,
 }
 
 pub struct Frame {
     pub data: Vec<u8>,
+    /// Same frame with its colors inverted, used for the adaptive-contrast
+    /// variant when the crosshair sits over a dark background.
+    pub inverted: Vec<u8>,
 }
 
-pub struct GifImage {
-    pub frames: Vec<GifFrame>,
+pub struct AnimatedImage {
+    pub frames: Vec<AnimatedFrame>,
     pub current_frame: usize,
     pub last_frame_time: Instant,
 }
 
 pub enum CrosshairImage {
     Static(Frame),
-    Gif(GifImage),
+    Animated(AnimatedImage),
 }
 
-fn process_buffer(buffer: RgbaImage, opacity: f32) -> Vec<u8> {
+fn process_buffer(buffer: &RgbaImage, opacity: f32) -> Vec<u8> {
     let (w, h) = buffer.dimensions();
     let mut data = Vec::with_capacity((w * h * 4) as usize);
 
@@ -48,6 +54,17 @@ fn process_buffer(buffer: RgbaImage, opacity: f32) -> Vec<u8> {
     data
 }
 
+/// Inverts the RGB channels of `buffer`, leaving alpha untouched.
+fn invert_rgb(buffer: &RgbaImage) -> RgbaImage {
+    let mut inverted = buffer.clone();
+    for pixel in inverted.pixels_mut() {
+        pixel.0[0] = 255 - pixel.0[0];
+        pixel.0[1] = 255 - pixel.0[1];
+        pixel.0[2] = 255 - pixel.0[2];
+    }
+    inverted
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum LoadImageError {
     #[error("Failed to load image: {0}")]
@@ -58,6 +75,43 @@ pub enum LoadImageError {
 
     #[error("Failed to detect image format")]
     UnknownFormat,
+
+    #[error("Image reports an animation but has no frames")]
+    EmptyAnimation,
+}
+
+/// Decodes `path` as an animation if `format` supports one, returning `None` for formats
+/// that are always static as well as for container formats (PNG, WebP) that happen to
+/// hold just a single frame, so the caller falls back to the static path either way.
+fn decode_animation(
+    format: ImageFormat,
+    path: &Path,
+) -> Result<Option<Vec<image::Frame>>, LoadImageError> {
+    let frames = match format {
+        ImageFormat::Gif => {
+            let decoder = GifDecoder::new(BufReader::new(File::open(path)?))?;
+            Some(decoder.into_frames().collect_frames()?)
+        }
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(BufReader::new(File::open(path)?))?;
+            if decoder.is_apng()? {
+                Some(decoder.apng()?.into_frames().collect_frames()?)
+            } else {
+                None
+            }
+        }
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(BufReader::new(File::open(path)?))?;
+            if decoder.has_animation() {
+                Some(decoder.into_frames().collect_frames()?)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    Ok(frames.filter(|frames| frames.len() > 1))
 }
 
 pub fn load_image(
@@ -68,43 +122,46 @@ pub fn load_image(
     let reader = image::ImageReader::open(path)?;
     let format = reader.format().ok_or(LoadImageError::UnknownFormat)?;
 
-    let (w, h, image) = match format {
-        ImageFormat::Gif => {
-            let file_in = BufReader::new(File::open(path)?);
-            let decoder = GifDecoder::new(file_in)?;
-
-            let (w, h) = decoder.dimensions();
-            let frames = decoder
-                .into_frames()
-                .collect_frames()?
-                .into_iter()
-                .map(|frame| {
-                    let delay_ms = frame.delay().numer_denom_ms().0 as u128;
-                    let buffer = frame.into_buffer();
-                    let data = process_buffer(buffer, opacity);
-
-                    GifFrame { data, delay_ms }
-                })
-                .collect();
-
-            (
-                w,
-                h,
-                CrosshairImage::Gif(GifImage {
-                    frames,
-                    current_frame: 0,
-                    last_frame_time: Instant::now(),
-                }),
-            )
-        }
-        _ => {
-            let image = reader.decode()?;
-            let (w, h) = image.dimensions();
-            let data = process_buffer(image.to_rgba8(), opacity);
+    if let Some(frames) = decode_animation(format, path)? {
+        let (w, h) = frames
+            .first()
+            .ok_or(LoadImageError::EmptyAnimation)?
+            .buffer()
+            .dimensions();
+
+        let frames = frames
+            .into_iter()
+            .map(|frame| {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                let delay_ms = (numer / denom.max(1)) as u128;
+                let buffer = frame.into_buffer();
+                let data = process_buffer(&buffer, opacity);
+                let inverted = process_buffer(&invert_rgb(&buffer), opacity);
+
+                AnimatedFrame {
+                    data,
+                    inverted,
+                    delay_ms,
+                }
+            })
+            .collect();
+
+        return Ok((
+            w,
+            h,
+            CrosshairImage::Animated(AnimatedImage {
+                frames,
+                current_frame: 0,
+                last_frame_time: Instant::now(),
+            }),
+        ));
+    }
 
-            (w, h, CrosshairImage::Static(Frame { data }))
-        }
-    };
+    let image = reader.decode()?;
+    let (w, h) = image.dimensions();
+    let buffer = image.to_rgba8();
+    let data = process_buffer(&buffer, opacity);
+    let inverted = process_buffer(&invert_rgb(&buffer), opacity);
 
-    Ok((w, h, image))
+    Ok((w, h, CrosshairImage::Static(Frame { data, inverted })))
 }
@@ -1,27 +1,25 @@
 use app::*;
+use cache_params::compute_image_hash;
 use clap::Parser;
-use crosshair::*;
-use image::{
-    AnimationDecoder, GenericImageView, ImageDecoder, ImageFormat, RgbaImage,
-    codecs::gif::GifDecoder,
-};
+use crosshair::load_image;
+use renderer::RendererKind;
 use smithay_client_toolkit::{
-    compositor::{CompositorState, Region},
+    compositor::CompositorState,
     delegate_compositor, delegate_layer, delegate_output, delegate_registry, delegate_shm,
     output::OutputState,
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
-    shell::{
-        WaylandSurface,
-        wlr_layer::{KeyboardInteractivity, Layer, LayerShell},
-    },
-    shm::{Shm, slot::SlotPool},
+    shell::wlr_layer::LayerShell,
+    shm::Shm,
 };
-use std::{fs::File, io::BufReader, time::Instant};
+use std::time::{Duration, Instant};
 use wayland_client::{Connection, globals::registry_queue_init};
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1;
 
 mod app;
+mod cache_params;
 mod crosshair;
+mod renderer;
 
 #[derive(Parser)]
 struct Args {
@@ -38,25 +36,21 @@ struct Args {
     /// range from 0 to 1
     #[arg(short, long, default_value_t = 1f32)]
     opacity: f32,
-}
 
-fn process_buffer(buffer: RgbaImage, opacity: f32) -> Vec<u8> {
-    let (w, h) = buffer.dimensions();
-    let mut data = Vec::with_capacity((w * h * 4) as usize);
+    /// Only show the crosshair on the output with this name (e.g. `DP-1`).
+    /// By default it is shown on every connected output.
+    #[arg(long)]
+    output: Option<String>,
 
-    for pixel in buffer.pixels() {
-        let [r, g, b, a] = pixel.0;
+    /// Rendering backend. `wgpu` uploads each frame once as a texture instead of
+    /// re-copying it into a shm buffer every frame, which matters for large animated crosshairs.
+    #[arg(long, value_enum, default_value = "shm")]
+    renderer: RendererKind,
 
-        // Calculate the premultiplied alpha
-        let alpha_f = (a as f32 * opacity) / 255f32;
-        let new_a = (a as f32 * opacity) as u8;
-        let new_r = (r as f32 * alpha_f) as u8;
-        let new_g = (g as f32 * alpha_f) as u8;
-        let new_b = (b as f32 * alpha_f) as u8;
-
-        data.extend_from_slice(&[new_b, new_g, new_r, new_a]);
-    }
-    data
+    /// Watch `image_path` for changes and hot-reload the crosshair when it's edited,
+    /// instead of requiring a restart.
+    #[arg(long)]
+    watch: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -65,47 +59,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load the image
     let args = Args::parse();
 
-    let image_path = &args.image_path;
-    let image_reader = image::ImageReader::open(image_path)?;
-    let format = image_reader.format().ok_or("Failed to read image format")?;
-
-    let (image_w, image_h, image) = match format {
-        ImageFormat::Gif => {
-            let file_in = BufReader::new(File::open(image_path)?);
-            let decoder = GifDecoder::new(file_in)?;
-
-            let (w, h) = decoder.dimensions();
-            let frames = decoder
-                .into_frames()
-                .collect_frames()?
-                .into_iter()
-                .map(|frame| {
-                    let delay_ms = frame.delay().numer_denom_ms().0 as u128;
-                    let buffer = frame.into_buffer();
-                    let data = process_buffer(buffer, args.opacity);
-
-                    GifFrame { data, delay_ms }
-                })
-                .collect();
-
-            (
-                w,
-                h,
-                CrosshairImage::Gif(GifImage {
-                    frames,
-                    current_frame: 0,
-                    last_frame_time: Instant::now(),
-                }),
-            )
-        }
-        _ => {
-            let image = image_reader.decode()?;
-            let (w, h) = image.dimensions();
-            let data = process_buffer(image.to_rgba8(), args.opacity);
-
-            (w, h, CrosshairImage::Static(Frame { data }))
-        }
-    };
+    let (image_w, image_h, image) = load_image(&args.image_path, args.opacity)?;
+    let image_hash = compute_image_hash(&args.image_path)?;
 
     // All Wayland apps start by connecting the compositor (server).
     let conn = Connection::connect_to_env()?;
@@ -116,37 +71,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // The compositor (not to be confused with the server which is commonly called the compositor) allows
     // configuring surfaces to be presented.
-    let compositor = CompositorState::bind(&globals, &qh)?;
+    let compositor_state = CompositorState::bind(&globals, &qh)?;
     // This app uses the wlr layer shell, which may not be available with every compositor.
     let layer_shell = LayerShell::bind(&globals, &qh)?;
-    // Since we are not using the GPU in this example, we use wl_shm to allow software rendering to a buffer
-    // we share with the compositor process.
+    // wl_shm backs the software renderer, and also the screencopy buffer used to sample the
+    // background for adaptive contrast.
     let shm = Shm::bind(&globals, &qh)?;
+    // Adaptive-contrast mode samples the screen behind the crosshair via this wlr-only
+    // protocol; not every compositor implements it, so we just disable the feature if it's missing.
+    let screencopy_manager = globals
+        .bind::<ZwlrScreencopyManagerV1, App, _>(&qh, 1..=3, ())
+        .ok();
 
-    // A layer surface is created from a surface.
-    let surface = compositor.create_surface(&qh);
-    // And then we create the layer shell.
-    let layer =
-        layer_shell.create_layer_surface(&qh, surface, Layer::Overlay, Some("rcrosshair"), None);
-    // Configure the layer surface, providing things like the anchor on screen, desired size and the keyboard
-    // interactivity
-    let region = Region::new(&compositor)?;
-    let wl_region = region.wl_region();
-    layer.wl_surface().set_input_region(Some(wl_region));
-    wl_region.destroy();
-
-    layer.set_exclusive_zone(-1);
-    layer.set_keyboard_interactivity(KeyboardInteractivity::None);
-    layer.set_size(image_w, image_h);
-
-    // In order for the layer surface to be mapped, we need to perform an initial commit with no attached\
-    // buffer. For more info, see WaylandSurface::commit
-    //
-    // The compositor will respond with an initial configure that we can then use to present to the layer
-    // surface with the correct options.
-    layer.commit();
-
-    let pool = SlotPool::new((image_w * image_h * 4) as usize, &shm)?;
     let target_x = args.target_x.unwrap_or(image_w / 2);
     let target_y = args.target_y.unwrap_or(image_h / 2);
     let mut rcrosshair = App {
@@ -154,22 +90,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // listen for seats and outputs.
         registry_state: RegistryState::new(&globals),
         output_state: OutputState::new(&globals, &qh),
+        compositor_state,
+        layer_shell,
         shm,
+        conn: conn.clone(),
+        renderer_kind: args.renderer,
+        screencopy_manager,
 
         exit: false,
-        first_configure: true,
-        pool,
-        width: image_w,
-        height: image_h,
-        layer,
+        surfaces: Vec::new(),
 
         image,
+        image_w,
+        image_h,
         target_x,
         target_y,
-        positioned: false,
+        output_name: args.output,
+
+        opacity: args.opacity,
+        image_path: args.image_path,
+        image_hash,
+        watch: args.watch,
+        target_x_arg: args.target_x,
+        target_y_arg: args.target_y,
+        last_watch_check: Instant::now() - Duration::from_secs(1),
     };
 
-    // We don't draw immediately, the configure will notify us when to first draw.
+    // A layer surface is created for every currently known output as part of this dispatch, and
+    // for every output that is hotplugged in afterwards (see `OutputHandler::new_output`). We
+    // don't draw immediately, each layer surface's configure will notify us when to first draw.
     loop {
         event_queue.blocking_dispatch(&mut rcrosshair)?;
 
@@ -0,0 +1,522 @@
+use crate::app::App;
+use crate::crosshair::CrosshairImage;
+use clap::ValueEnum;
+use smithay_client_toolkit::{
+    shell::{WaylandSurface, wlr_layer::LayerSurface},
+    shm::{
+        Shm,
+        slot::{ActivateSlotError, CreateBufferError, SlotPool},
+    },
+};
+use std::ptr::NonNull;
+use thiserror::Error;
+use wayland_client::{
+    Connection, QueueHandle,
+    protocol::{wl_shm, wl_surface},
+};
+
+/// Which backend presents the crosshair. Selected once at startup via `--renderer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RendererKind {
+    /// The original CPU path: blit premultiplied pixels into a `wl_shm` buffer every frame.
+    Shm,
+    /// Upload each frame once as a texture and composite with a GPU draw call.
+    Wgpu,
+}
+
+/// Builds the renderer selected by `kind` for a newly created output surface.
+pub fn create_renderer(
+    kind: RendererKind,
+    conn: &Connection,
+    shm: &Shm,
+    wl_surface: &wl_surface::WlSurface,
+    width: u32,
+    height: u32,
+    image: &CrosshairImage,
+) -> Result<Box<dyn Renderer>, RendererError> {
+    match kind {
+        RendererKind::Shm => Ok(Box::new(ShmRenderer::new(shm, width, height)?)),
+        RendererKind::Wgpu => Ok(Box::new(WgpuRenderer::new(
+            conn, wl_surface, width, height, image,
+        )?)),
+    }
+}
+
+/// The pixels to present for the current frame, handed to a [`Renderer`] by
+/// `App::draw` without it needing to know about [`CrosshairImage`]'s shape.
+pub struct FrameView<'a> {
+    /// Index of the current frame within the animation (always 0 for static images).
+    pub index: usize,
+    pub dark_background: bool,
+    /// Premultiplied BGRA8 bytes for the software path; ignored by the GPU path,
+    /// which already has every frame uploaded as a texture.
+    pub bytes: &'a [u8],
+    /// Whether the caller wants another `frame` callback after this present
+    /// (animating, or adaptive contrast needs to keep re-sampling).
+    pub request_next_frame: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RendererError {
+    #[error("Failed to create buffer: {0}")]
+    CreateBuffer(#[from] CreateBufferError),
+    #[error("Failed to activate slot: {0}")]
+    ActivateSlot(#[from] ActivateSlotError),
+    #[error("No compatible wgpu adapter found")]
+    NoAdapter,
+    #[error("Failed to request wgpu device: {0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+    #[error("Failed to create wgpu surface: {0}")]
+    CreateSurface(#[from] wgpu::CreateSurfaceError),
+    #[error("Failed to acquire next wgpu surface texture: {0}")]
+    SurfaceTexture(#[from] wgpu::SurfaceError),
+    #[error("Output surface is incompatible with the chosen wgpu adapter")]
+    IncompatibleSurface,
+}
+
+/// Presents a [`CrosshairImage`] onto a `LayerSurface`. `ShmRenderer` is the
+/// original CPU path; `WgpuRenderer` uploads each frame once and composites
+/// with draw calls instead of a full-resolution memcpy every frame.
+pub trait Renderer {
+    fn resize(&mut self, shm: &Shm, width: u32, height: u32) -> Result<(), RendererError>;
+    fn draw(
+        &mut self,
+        qh: &QueueHandle<App>,
+        layer: &LayerSurface,
+        width: u32,
+        height: u32,
+        frame: FrameView<'_>,
+    ) -> Result<(), RendererError>;
+
+    /// Pushes a newly (re)loaded `CrosshairImage` into already-created GPU resources, for
+    /// `--watch` live reload. The shm path is a no-op: it re-copies `FrameView::bytes` fresh
+    /// on every `draw`, so there's nothing baked in to refresh.
+    fn reload(&mut self, _width: u32, _height: u32, _image: &CrosshairImage) {}
+}
+
+pub struct ShmRenderer {
+    pool: SlotPool,
+}
+
+impl ShmRenderer {
+    pub fn new(shm: &Shm, width: u32, height: u32) -> Result<Self, RendererError> {
+        Ok(Self {
+            pool: SlotPool::new((width * height * 4) as usize, shm)?,
+        })
+    }
+}
+
+impl Renderer for ShmRenderer {
+    fn resize(&mut self, shm: &Shm, width: u32, height: u32) -> Result<(), RendererError> {
+        let needed = (width * height * 4) as usize;
+        if needed > self.pool.len() {
+            self.pool = SlotPool::new(needed, shm)?;
+        }
+        Ok(())
+    }
+
+    fn draw(
+        &mut self,
+        qh: &QueueHandle<App>,
+        layer: &LayerSurface,
+        width: u32,
+        height: u32,
+        frame: FrameView<'_>,
+    ) -> Result<(), RendererError> {
+        let stride = width * 4;
+        let (buffer, canvas) = self.pool.create_buffer(
+            width as i32,
+            height as i32,
+            stride as i32,
+            wl_shm::Format::Argb8888,
+        )?;
+
+        canvas.fill(0);
+        canvas[..frame.bytes.len()].copy_from_slice(frame.bytes);
+
+        if frame.request_next_frame {
+            layer
+                .wl_surface()
+                .frame(qh, layer.wl_surface().clone());
+        }
+        layer
+            .wl_surface()
+            .damage_buffer(0, 0, width as i32, height as i32);
+        buffer.attach_to(layer.wl_surface())?;
+        layer.commit();
+
+        Ok(())
+    }
+}
+
+/// A texture uploaded once per animation frame, in both the normal and
+/// adaptive-contrast (inverted) variant.
+struct GpuFrame {
+    bind_groups: [wgpu::BindGroup; 2],
+}
+
+pub struct WgpuRenderer {
+    surface: wgpu::Surface<'static>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    pipeline: wgpu::RenderPipeline,
+    frames: Vec<GpuFrame>,
+    // Kept around (rather than dropped after `new`) so `reload` can re-upload frames
+    // without rebuilding the device/pipeline for `--watch`.
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+const SHADER_SRC: &str = r#"
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) idx: u32) -> VertexOutput {
+    var positions = array<vec2<f32>, 3>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(3.0, -1.0),
+        vec2<f32>(-1.0, 3.0),
+    );
+    let pos = positions[idx];
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(pos, 0.0, 1.0);
+    out.uv = vec2<f32>((pos.x + 1.0) * 0.5, 1.0 - (pos.y + 1.0) * 0.5);
+    return out;
+}
+
+@group(0) @binding(0) var crosshair_texture: texture_2d<f32>;
+@group(0) @binding(1) var crosshair_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(crosshair_texture, crosshair_sampler, in.uv);
+}
+"#;
+
+impl WgpuRenderer {
+    pub fn new(
+        conn: &Connection,
+        wl_surface: &wl_surface::WlSurface,
+        width: u32,
+        height: u32,
+        image: &CrosshairImage,
+    ) -> Result<Self, RendererError> {
+        use wayland_client::Proxy;
+
+        let instance = wgpu::Instance::default();
+
+        // Safety: `wl_surface` and the connection's `wl_display` both outlive this renderer.
+        // `OutputSurface` declares `renderer` before `layer`, so this `wgpu::Surface` is
+        // dropped before the `wl_surface` it was built from.
+        let surface = unsafe {
+            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::RawHandles {
+                raw_display_handle: raw_window_handle::RawDisplayHandle::Wayland(
+                    raw_window_handle::WaylandDisplayHandle::new(
+                        NonNull::new(conn.backend().display_ptr() as *mut _)
+                            .expect("non-null wl_display"),
+                    ),
+                ),
+                raw_window_handle: raw_window_handle::RawWindowHandle::Wayland(
+                    raw_window_handle::WaylandWindowHandle::new(
+                        NonNull::new(wl_surface.id().as_ptr() as *mut _)
+                            .expect("non-null wl_surface"),
+                    ),
+                ),
+            })?
+        };
+
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        }))
+        .ok_or(RendererError::NoAdapter)?;
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor::default(),
+            None,
+        ))?;
+
+        let config = surface
+            .get_default_config(&adapter, width, height)
+            .ok_or(RendererError::IncompatibleSurface)?;
+        surface.configure(&device, &config);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("rcrosshair texture bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("rcrosshair shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER_SRC.into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("rcrosshair pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // The uploaded frames are already premultiplied by `process_buffer` (the same bytes the
+        // shm path uses), so the blend state uses the premultiplied-alpha equation
+        // (`One`, `OneMinusSrcAlpha`); no premultiplication is done on the GPU.
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("rcrosshair pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let frames = upload_frames(
+            &device,
+            &queue,
+            &bind_group_layout,
+            &sampler,
+            width,
+            height,
+            image,
+        );
+
+        Ok(Self {
+            surface,
+            device,
+            queue,
+            config,
+            pipeline,
+            frames,
+            bind_group_layout,
+            sampler,
+        })
+    }
+}
+
+/// Uploads every animation frame (in both contrast variants) once, up front,
+/// so drawing is just picking the right already-resident bind group.
+fn upload_frames(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    width: u32,
+    height: u32,
+    image: &CrosshairImage,
+) -> Vec<GpuFrame> {
+    let variants: Vec<[&[u8]; 2]> = match image {
+        CrosshairImage::Static(frame) => vec![[&frame.data, &frame.inverted]],
+        CrosshairImage::Animated(animated) => animated
+            .frames
+            .iter()
+            .map(|frame| [frame.data.as_slice(), frame.inverted.as_slice()])
+            .collect(),
+    };
+
+    variants
+        .into_iter()
+        .map(|[normal, inverted]| GpuFrame {
+            bind_groups: [
+                upload_texture(device, queue, layout, sampler, width, height, normal),
+                upload_texture(device, queue, layout, sampler, width, height, inverted),
+            ],
+        })
+        .collect()
+}
+
+fn upload_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> wgpu::BindGroup {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+
+    // `data` is already premultiplied BGRA8, the same byte layout as `Bgra8Unorm`, so it can be
+    // uploaded as-is.
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("rcrosshair frame"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Bgra8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width * 4),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("rcrosshair frame bind group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+impl Renderer for WgpuRenderer {
+    fn resize(&mut self, _shm: &Shm, width: u32, height: u32) -> Result<(), RendererError> {
+        if width != self.config.width || height != self.config.height {
+            self.config.width = width;
+            self.config.height = height;
+            self.surface.configure(&self.device, &self.config);
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self, width: u32, height: u32, image: &CrosshairImage) {
+        self.frames = upload_frames(
+            &self.device,
+            &self.queue,
+            &self.bind_group_layout,
+            &self.sampler,
+            width,
+            height,
+            image,
+        );
+    }
+
+    fn draw(
+        &mut self,
+        qh: &QueueHandle<App>,
+        layer: &LayerSurface,
+        _width: u32,
+        _height: u32,
+        frame: FrameView<'_>,
+    ) -> Result<(), RendererError> {
+        let gpu_frame = &self.frames[frame.index.min(self.frames.len() - 1)];
+        let bind_group = &gpu_frame.bind_groups[frame.dark_background as usize];
+
+        let surface_texture = self.surface.get_current_texture()?;
+        let view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("rcrosshair encoder"),
+            });
+
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("rcrosshair pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        self.queue.submit(Some(encoder.finish()));
+        surface_texture.present();
+
+        // Request the next frame callback so animated crosshairs keep swapping bound textures,
+        // and so adaptive contrast keeps getting a chance to re-sample the background.
+        if frame.request_next_frame {
+            layer
+                .wl_surface()
+                .frame(qh, layer.wl_surface().clone());
+        }
+        layer.commit();
+
+        Ok(())
+    }
+}